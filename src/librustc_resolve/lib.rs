@@ -0,0 +1,91 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rustc;
+extern crate rustc_data_structures;
+extern crate syntax;
+
+use rustc::hir::map::Definitions;
+use rustc::session::Session;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use std::cell::RefCell;
+use std::rc::Rc;
+use syntax::ast;
+use syntax::ext::base::MultiItemModifier;
+use syntax::ext::base::SyntaxExtension;
+use syntax::ext::hygiene::Mark;
+
+mod build_reduced_graph;
+mod macros;
+
+pub use macros::ExpansionData;
+
+pub struct ModuleS<'a> {
+    pub parent: Option<Module<'a>>,
+    pub macros: RefCell<FxHashMap<ast::Name, Rc<SyntaxExtension>>>,
+    pub macros_escape: bool,
+    name: Option<ast::Name>,
+    def_id: Option<rustc::hir::def_id::DefId>,
+}
+
+pub type Module<'a> = &'a ModuleS<'a>;
+
+impl<'a> ModuleS<'a> {
+    pub fn def_id(&self) -> Option<rustc::hir::def_id::DefId> {
+        self.def_id
+    }
+
+    pub fn name(&self) -> Option<ast::Name> {
+        self.name
+    }
+}
+
+pub struct Resolver<'a> {
+    session: &'a Session,
+
+    definitions: Definitions,
+
+    // The crate root, and the module currently being visited during expansion.
+    graph_root: Module<'a>,
+    current_module: Module<'a>,
+    module_map: FxHashMap<ast::NodeId, Module<'a>>,
+
+    // Per-expansion bookkeeping, keyed by `Mark::as_u32()`.
+    expansion_data: FxHashMap<u32, ExpansionData<'a>>,
+    macros_at_scope: FxHashMap<ast::NodeId, Vec<Mark>>,
+
+    // Flat, crate-wide macro namespaces.
+    macro_names: FxHashSet<ast::Name>,
+    attr_macro_names: FxHashSet<ast::Name>,
+    macro_defs: FxHashMap<ast::Name, Module<'a>>,
+    derive_modes: FxHashMap<ast::Name, Rc<MultiItemModifier>>,
+    exported_macros: Vec<ast::MacroDef>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(session: &'a Session, definitions: Definitions, graph_root: Module<'a>) -> Self {
+        let mut resolver = Resolver {
+            session: session,
+            definitions: definitions,
+            graph_root: graph_root,
+            current_module: graph_root,
+            module_map: FxHashMap::default(),
+            expansion_data: FxHashMap::default(),
+            macros_at_scope: FxHashMap::default(),
+            macro_names: FxHashSet::default(),
+            attr_macro_names: FxHashSet::default(),
+            macro_defs: FxHashMap::default(),
+            derive_modes: FxHashMap::default(),
+            exported_macros: Vec::new(),
+        };
+        resolver.expansion_data.insert(0, ExpansionData::root(graph_root));
+        resolver
+    }
+}
@@ -8,7 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use {Module, Resolver};
+use {Module, Resolver, UseLexicalScopeFlag};
+use ResolveResult::{Failed, Indeterminate, Success};
 use build_reduced_graph::BuildReducedGraphVisitor;
 use rustc::hir::def_id::{CRATE_DEF_INDEX, DefIndex};
 use rustc::hir::map::{self, DefCollector};
@@ -30,6 +31,14 @@ pub struct ExpansionData<'a> {
     // True if this expansion is in a `const_integer` position, for example `[u32; m!()]`.
     // c.f. `DefCollector::visit_ast_const_integer`.
     const_integer: bool,
+    // How many macro expansions deep this invocation is nested; the root is 0.
+    depth: usize,
+    // The mark of the expansion that produced this one, if any. Following this chain
+    // reconstructs a backtrace of macro invocations, c.f. `Resolver::macro_backtrace`.
+    parent: Option<Mark>,
+    // The name of the macro that produced this expansion, filled in by `resolve_invoc`
+    // once it is known; used together with `parent` to print the backtrace.
+    macro_name: Option<ast::Name>,
 }
 
 impl<'a> ExpansionData<'a> {
@@ -38,6 +47,9 @@ impl<'a> ExpansionData<'a> {
             module: graph_root,
             def_index: CRATE_DEF_INDEX,
             const_integer: false,
+            depth: 0,
+            parent: None,
+            macro_name: None,
         }
     }
 }
@@ -54,6 +66,9 @@ impl<'a> base::Resolver for Resolver<'a> {
             module: module,
             def_index: module.def_id().unwrap().index,
             const_integer: false,
+            depth: 0,
+            parent: None,
+            macro_name: None,
         });
         mark
     }
@@ -74,6 +89,8 @@ impl<'a> base::Resolver for Resolver<'a> {
         }
         if def.export {
             def.id = self.next_node_id();
+            let module = self.expansion_data[&scope.as_u32()].module;
+            self.macro_defs.entry(def.ident.name).or_insert(module);
             self.exported_macros.push(def);
         }
     }
@@ -82,11 +99,15 @@ impl<'a> base::Resolver for Resolver<'a> {
         if let NormalTT(..) = *ext {
             self.macro_names.insert(ident.name);
         }
+        if let MultiModifier(..) | MultiDecorator(..) | SyntaxExtension::AttrProcMacro(..) = *ext {
+            self.attr_macro_names.insert(ident.name);
+        }
 
         let mut module = self.expansion_data[&scope.as_u32()].module;
         while module.macros_escape {
             module = module.parent.unwrap();
         }
+        self.macro_defs.entry(ident.name).or_insert(module);
         module.macros.borrow_mut().insert(ident.name, ext);
     }
 
@@ -111,24 +132,69 @@ impl<'a> base::Resolver for Resolver<'a> {
     }
 
     fn resolve_invoc(&mut self, scope: Mark, invoc: &Invocation) -> Option<Rc<SyntaxExtension>> {
-        let (name, span) = match invoc.kind {
+        let is_bang = match invoc.kind {
+            InvocationKind::Bang { .. } => true,
+            InvocationKind::Attr { .. } => false,
+        };
+        let (name, span, qualified_module) = match invoc.kind {
             InvocationKind::Bang { ref mac, .. } => {
                 let path = &mac.node.path;
-                if path.segments.len() > 1 || path.global ||
-                   !path.segments[0].parameters.is_empty() {
+                if path.segments.iter().any(|segment| !segment.parameters.is_empty()) {
                     self.session.span_err(path.span,
                                           "expected macro name without module separators");
                     return None;
                 }
-                (path.segments[0].identifier.name, path.span)
+                let name = path.segments.last().unwrap().identifier.name;
+                if path.segments.len() > 1 || path.global {
+                    let module = match self.resolve_macro_module_path(scope, path) {
+                        Some(module) => module,
+                        None => return None,
+                    };
+                    (name, path.span, Some(module))
+                } else {
+                    (name, path.span, None)
+                }
             }
-            InvocationKind::Attr { ref attr, .. } => (intern(&*attr.name()), attr.span),
+            InvocationKind::Attr { ref attr, .. } => (intern(&*attr.name()), attr.span, None),
         };
 
+        {
+            let data = self.expansion_data.get_mut(&scope.as_u32()).unwrap();
+            data.macro_name = Some(name);
+            if data.depth > self.session.recursion_limit.get() {
+                let mut err = self.session.struct_span_err(
+                    span, &format!("recursion limit reached while expanding the macro `{}`",
+                                    name));
+                err.note(&self.macro_backtrace(scope));
+                err.help(&format!("consider adding a `#![recursion_limit = \"{}\"]` attribute \
+                                    to your crate", self.session.recursion_limit.get() * 2));
+                err.emit();
+                return None;
+            }
+        }
+
+        if let Some(module) = qualified_module {
+            return match module.macros.borrow().get(&name).and_then(|ext| {
+                Resolver::bang_compatible_ext(ext, is_bang)
+            }) {
+                Some(ext) => Some(ext),
+                None => {
+                    let mut err = self.session.struct_span_err(
+                        span, &format!("macro undefined: '{}!'", name));
+                    self.suggest_macro_name(name, &mut err);
+                    err.emit();
+                    None
+                }
+            };
+        }
+
         let mut module = self.expansion_data[&scope.as_u32()].module;
         loop {
-            if let Some(ext) = module.macros.borrow().get(&name) {
-                return Some(ext.clone());
+            let found = module.macros.borrow().get(&name).and_then(|ext| {
+                Resolver::bang_compatible_ext(ext, is_bang)
+            });
+            if let Some(ext) = found {
+                return Some(ext);
             }
             match module.parent {
                 Some(parent) => module = parent,
@@ -138,7 +204,7 @@ impl<'a> base::Resolver for Resolver<'a> {
 
         let mut err =
             self.session.struct_span_err(span, &format!("macro undefined: '{}!'", name));
-        self.suggest_macro_name(&name.as_str(), &mut err);
+        self.suggest_macro_name(name, &mut err);
         err.emit();
         None
     }
@@ -149,24 +215,134 @@ impl<'a> base::Resolver for Resolver<'a> {
 }
 
 impl<'a> Resolver<'a> {
-    fn suggest_macro_name(&mut self, name: &str, err: &mut DiagnosticBuilder<'a>) {
-        if let Some(suggestion) = find_best_match_for_name(self.macro_names.iter(), name, None) {
-            if suggestion != name {
-                err.help(&format!("did you mean `{}!`?", suggestion));
+    // A module's `macros` map holds every kind of syntax extension registered in it,
+    // not just `macro_rules!`-style bang macros (c.f. `find_attr_invoc`, which does the
+    // same kind of filtering for attributes). A `Bang` invocation may only resolve to a
+    // `NormalTT`; anything else (a derive mode, an attribute modifier/decorator, ...) is
+    // treated as absent so that `resolve_invoc` falls through to its "undefined" + the
+    // appropriate suggestion, rather than silently invoking the wrong kind of extension.
+    fn bang_compatible_ext(ext: &Rc<SyntaxExtension>, is_bang: bool) -> Option<Rc<SyntaxExtension>> {
+        if !is_bang {
+            return Some(ext.clone());
+        }
+        match **ext {
+            NormalTT(..) => Some(ext.clone()),
+            _ => None,
+        }
+    }
+
+    // Resolves the module-path prefix of a path-qualified macro invocation, e.g. the
+    // `foo::bar` in `foo::bar!()` or the `::crate::macros` in `::crate::macros::bar!()`,
+    // to the `Module` whose `macros` map the final segment should be looked up in.
+    fn resolve_macro_module_path(&mut self, scope: Mark, path: &ast::Path) -> Option<Module<'a>> {
+        let prefix = &path.segments[..path.segments.len() - 1];
+        if prefix.is_empty() {
+            return Some(if path.global {
+                self.graph_root
             } else {
-                err.help(&format!("have you added the `#[macro_use]` on the module/import?"));
+                self.expansion_data[&scope.as_u32()].module
+            });
+        }
+
+        let idents: Vec<_> = prefix.iter().map(|segment| segment.identifier).collect();
+        let use_lexical_scope = if path.global {
+            UseLexicalScopeFlag::DontUseLexicalScope
+        } else {
+            UseLexicalScopeFlag::UseLexicalScope
+        };
+        match self.resolve_module_path(&idents, use_lexical_scope, path.span) {
+            Success(module) => Some(module),
+            Indeterminate => None,
+            Failed(Some((span, msg))) => {
+                self.session.span_err(span, &msg);
+                None
+            }
+            Failed(None) => {
+                self.session.span_err(path.span, "unresolved module path in macro invocation");
+                None
+            }
+        }
+    }
+
+    // Produces a help message for a macro invocation that failed to resolve, trying
+    // progressively less specific explanations: a derive mode with the same name, a
+    // known attribute macro, a macro that is defined somewhere in the crate but not
+    // reachable from this scope, and finally a plain Levenshtein-distance suggestion.
+    fn suggest_macro_name(&mut self, name: ast::Name, err: &mut DiagnosticBuilder<'a>) {
+        let name_str = &*name.as_str();
+
+        if self.derive_modes.contains_key(&name) {
+            err.help(&format!("a derive mode named `{}` exists; did you mean `#[derive({})]`?",
+                               name_str, name_str));
+            return;
+        }
+
+        if self.attr_macro_names.contains(&name) {
+            err.help(&format!("`{}` is an attribute macro; did you mean `#[{}]`?",
+                               name_str, name_str));
+            return;
+        }
+
+        if let Some(&module) = self.macro_defs.get(&name) {
+            let path = self.module_to_string(module);
+            err.help(&format!("a macro named `{}` is defined in module `{}`; \
+                                try `#[macro_use] use {}::{};` or `use {}::{};`",
+                               name_str, path, path, name_str, path, name_str));
+            return;
+        }
+
+        if let Some(suggestion) = find_best_match_for_name(self.macro_names.iter(), name_str, None) {
+            if suggestion != name_str {
+                err.help(&format!("did you mean `{}!`?", suggestion));
+                return;
+            }
+        }
+
+        err.help(&format!("have you added the `#[macro_use]` on the module/import?"));
+    }
+
+    // Reconstructs the chain of macro invocations leading to `mark` by following the
+    // `parent` links stored in `ExpansionData`, for use in recursion-limit diagnostics.
+    fn macro_backtrace(&self, mark: Mark) -> String {
+        let mut trace = Vec::new();
+        let mut current = Some(mark);
+        while let Some(mark) = current {
+            let data = &self.expansion_data[&mark.as_u32()];
+            if let Some(name) = data.macro_name {
+                trace.push(format!("{}!", name));
+            }
+            current = data.parent;
+        }
+        trace.reverse();
+        format!("in expansion of {}", trace.join(" => "))
+    }
+
+    // Renders the `::`-separated path of a module, for use in diagnostics.
+    fn module_to_string(&self, module: Module<'a>) -> String {
+        let mut names = Vec::new();
+        let mut module = Some(module);
+        while let Some(m) = module {
+            if let Some(name) = m.name() {
+                names.push(name.as_str().to_string());
             }
+            module = m.parent;
         }
+        names.reverse();
+        names.join("::")
     }
 
     fn collect_def_ids(&mut self, mark: Mark, expansion: &Expansion) {
         let expansion_data = &mut self.expansion_data;
-        let ExpansionData { def_index, const_integer, module } = expansion_data[&mark.as_u32()];
+        let ExpansionData { def_index, const_integer, module, depth, .. } =
+            expansion_data[&mark.as_u32()];
         let visit_macro_invoc = &mut |invoc: map::MacroInvocationData| {
             expansion_data.entry(invoc.id.as_u32()).or_insert(ExpansionData {
                 def_index: invoc.def_index,
                 const_integer: invoc.const_integer,
                 module: module,
+                depth: depth + 1,
+                parent: Some(mark),
+                macro_name: None,
             });
         };
 
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Path-qualified macro invocations resolve like other items: the module path is
+// walked segment by segment, and only the final module's macro namespace is searched.
+
+mod inner {}
+
+fn main() {
+    nonexistent::mac!();
+    //~^ ERROR unresolved
+
+    inner::mac!();
+    //~^ ERROR macro undefined
+
+    Vec::<u8>::mac!();
+    //~^ ERROR expected macro name without module separators
+}
@@ -0,0 +1,26 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A macro that recurses into itself past `recursion_limit` should fail with a
+// bounded diagnostic that prints the chain of macro names involved, instead of
+// overflowing the stack.
+
+#![recursion_limit = "8"]
+
+macro_rules! recurse {
+    () => { recurse!(); };
+    //~^ ERROR recursion limit reached while expanding the macro `recurse`
+    //~| NOTE in expansion of recurse!
+    //~| HELP consider adding a `#![recursion_limit
+}
+
+fn main() {
+    recurse!();
+}
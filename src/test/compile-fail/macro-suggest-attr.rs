@@ -0,0 +1,23 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:macro_suggest_plugin.rs
+
+#![feature(plugin)]
+#![plugin(macro_suggest_plugin)]
+
+// Writing `my_suggest_attr!()` instead of `#[my_suggest_attr]` should point the
+// user at the attribute of the same name rather than the generic `#[macro_use]` hint.
+
+fn main() {
+    my_suggest_attr!();
+    //~^ ERROR macro undefined
+    //~| HELP did you mean `#[my_suggest_attr]`
+}
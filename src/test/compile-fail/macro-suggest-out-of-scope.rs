@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A macro defined in a sibling module that isn't `#[macro_use]`d or imported
+// should be named explicitly in the diagnostic, not just covered by the
+// generic "have you added `#[macro_use]`" catch-all.
+
+mod helpers {
+    macro_rules! helper {
+        () => {}
+    }
+}
+
+fn main() {
+    helper!();
+    //~^ ERROR macro undefined
+    //~| HELP is defined in module `helpers`
+}
@@ -0,0 +1,25 @@
+// Copyright 2016 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Macros are module-scoped items: a path-qualified invocation resolves by
+// walking the module graph, just like any other item path would.
+
+mod produces {
+    macro_rules! one {
+        () => { 1 }
+    }
+}
+
+fn main() {
+    let a = produces::one!();
+    let b = ::produces::one!();
+    assert_eq!(a, 1);
+    assert_eq!(b, 1);
+}